@@ -79,6 +79,31 @@ pub enum Variadic {
     Variadic
 }
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+/// Conditional-compilation expression, for gating a declaration behind
+/// `#if`/`#ifdef`
+pub enum Cfg<'a> {
+    /// token is `#define`d
+    Defined(&'a str),
+    /// token is not `#define`d
+    NotDefined(&'a str),
+    /// token expands to exactly this value
+    Eq(&'a str, &'a str),
+    /// token compared against a value with an arbitrary operator
+    Compare {
+	tok: &'a str,
+	op: &'a str,
+	val: &'a str
+    },
+    /// every sub-expression must hold
+    All(&'a [Cfg<'a>]),
+    /// at least one sub-expression must hold
+    Any(&'a [Cfg<'a>]),
+    /// sub-expression must not hold
+    Not(&'a Cfg<'a>)
+}
+
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Copy, Hash, Debug, Default)]
 /// Header
@@ -92,6 +117,16 @@ pub struct Header<'a> {
     num_macros: usize,
     types_ptr: usize, // *const Type<'a>
     num_types: usize,
+    structs_ptr: usize, // *const Struct<'a>
+    num_structs: usize,
+    unions_ptr: usize, // *const Union<'a>
+    num_unions: usize,
+    enums_ptr: usize, // *const Enum<'a>
+    num_enums: usize,
+    classes_ptr: usize, // *const Class<'a>
+    num_classes: usize,
+    namespaces_ptr: usize, // *const Namespace<'a>
+    num_namespaces: usize,
     cxx: CXX,
     extra: Option<&'a str>,
     post_extra: Option<&'a str>
@@ -112,11 +147,22 @@ impl<'a> Header<'a> {
     ///
     /// types - typedefs
     ///
+    /// structs - struct declarations
+    ///
+    /// unions - union declarations
+    ///
+    /// enums - enum declarations
+    ///
+    /// classes - C++ class declarations, typically only populated for `CXX::CXXOnly` headers
+    ///
+    /// namespaces - C++ namespaces, typically only populated for `CXX::CXXOnly` headers
+    ///
     /// cxx - language support
     ///
     /// extra - other symbols
     ///
     /// post_extra - other symbols (after end of include guard)
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
 	path: Option<&'a str>,
 	name: &'a str,
@@ -124,6 +170,11 @@ impl<'a> Header<'a> {
 	funcs: &'a [Func<'a>],
 	macros: &'a [Macro<'a>],
 	types: &'a [Type<'a>],
+	structs: &'a [Struct<'a>],
+	unions: &'a [Union<'a>],
+	enums: &'a [Enum<'a>],
+	classes: &'a [Class<'a>],
+	namespaces: &'a [Namespace<'a>],
 	cxx: CXX,
 	extra: Option<&'a str>,
 	post_extra: Option<&'a str>
@@ -131,6 +182,11 @@ impl<'a> Header<'a> {
 	let funcs_ptr = funcs.as_ptr() as usize;
 	let macros_ptr=  macros.as_ptr() as usize;
 	let types_ptr = types.as_ptr() as usize;
+	let structs_ptr = structs.as_ptr() as usize;
+	let unions_ptr = unions.as_ptr() as usize;
+	let enums_ptr = enums.as_ptr() as usize;
+	let classes_ptr = classes.as_ptr() as usize;
+	let namespaces_ptr = namespaces.as_ptr() as usize;
 	Self {
 	    path,
 	    name,
@@ -138,12 +194,22 @@ impl<'a> Header<'a> {
 	    funcs_ptr,
 	    macros_ptr,
 	    types_ptr,
+	    structs_ptr,
+	    unions_ptr,
+	    enums_ptr,
+	    classes_ptr,
+	    namespaces_ptr,
 	    extra,
 	    post_extra,
 	    cxx,
 	    num_funcs: funcs.len(),
 	    num_types: types.len(),
-	    num_macros: macros.len()
+	    num_macros: macros.len(),
+	    num_structs: structs.len(),
+	    num_unions: unions.len(),
+	    num_enums: enums.len(),
+	    num_classes: classes.len(),
+	    num_namespaces: namespaces.len()
 	}
     }
 
@@ -158,7 +224,7 @@ impl<'a> Header<'a> {
     }
 
     /// header guard
-    pub fn guard(&self) -> Option<HeaderGuard> {
+    pub fn guard(&self) -> Option<HeaderGuard<'a>> {
 	self.guard
     }
 
@@ -183,6 +249,41 @@ impl<'a> Header<'a> {
 	}
     }
 
+    /// struct declarations
+    pub fn structs(&self) -> &'a [Struct<'a>] {
+	unsafe {
+	    core::slice::from_raw_parts(self.structs_ptr as *const Struct<'a>, self.num_structs)
+	}
+    }
+
+    /// union declarations
+    pub fn unions(&self) -> &'a [Union<'a>] {
+	unsafe {
+	    core::slice::from_raw_parts(self.unions_ptr as *const Union<'a>, self.num_unions)
+	}
+    }
+
+    /// enum declarations
+    pub fn enums(&self) -> &'a [Enum<'a>] {
+	unsafe {
+	    core::slice::from_raw_parts(self.enums_ptr as *const Enum<'a>, self.num_enums)
+	}
+    }
+
+    /// C++ class declarations
+    pub fn classes(&self) -> &'a [Class<'a>] {
+	unsafe {
+	    core::slice::from_raw_parts(self.classes_ptr as *const Class<'a>, self.num_classes)
+	}
+    }
+
+    /// C++ namespaces
+    pub fn namespaces(&self) -> &'a [Namespace<'a>] {
+	unsafe {
+	    core::slice::from_raw_parts(self.namespaces_ptr as *const Namespace<'a>, self.num_namespaces)
+	}
+    }
+
     /// language support
     pub fn cxx(&self) -> CXX {
 	self.cxx
@@ -204,7 +305,8 @@ impl<'a> Header<'a> {
 /// Typedef
 pub struct Type<'a> {
     name: &'a str,
-    r#type: &'a str
+    r#type: &'a str,
+    cfg: Option<Cfg<'a>>
 }
 
 impl<'a> Type<'a> {
@@ -213,13 +315,17 @@ impl<'a> Type<'a> {
     /// name - name of typedef
     ///
     /// type - type of typedef
+    ///
+    /// cfg - conditional-compilation guard, if any
     pub fn new(
 	name: &'a str,
-	r#type: &'a str
+	r#type: &'a str,
+	cfg: Option<Cfg<'a>>
     ) -> Self {
 	Self {
 	    name,
-	    r#type
+	    r#type,
+	    cfg
 	}
     }
 
@@ -232,6 +338,467 @@ impl<'a> Type<'a> {
     pub fn r#type(&self) -> &'a str {
 	self.r#type
     }
+
+    /// conditional-compilation guard
+    pub fn cfg(&self) -> Option<Cfg<'a>> {
+	self.cfg
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Hash, Debug, Default)]
+/// struct/union member
+pub struct Field<'a> {
+    r#type: &'a str,
+    name: &'a str,
+    bitfield: Option<u32>,
+    packed: bool,
+    align: Option<u32>
+}
+
+impl<'a> Field<'a> {
+    /// Create new field
+    ///
+    /// type - type of member
+    ///
+    /// name - name of member
+    ///
+    /// bitfield - bitfield width, if any
+    ///
+    /// packed - emit `__attribute__((packed))`
+    ///
+    /// align - emit `__attribute__((aligned(N)))`, if any
+    pub fn new(
+	r#type: &'a str,
+	name: &'a str,
+	bitfield: Option<u32>,
+	packed: bool,
+	align: Option<u32>
+    ) -> Self {
+	Self {
+	    r#type,
+	    name,
+	    bitfield,
+	    packed,
+	    align
+	}
+    }
+
+    /// type of member
+    pub fn r#type(&self) -> &'a str {
+	self.r#type
+    }
+
+    /// name of member
+    pub fn name(&self) -> &'a str {
+	self.name
+    }
+
+    /// bitfield width
+    pub fn bitfield(&self) -> Option<u32> {
+	self.bitfield
+    }
+
+    /// whether `__attribute__((packed))` is emitted
+    pub fn packed(&self) -> bool {
+	self.packed
+    }
+
+    /// `__attribute__((aligned(N)))`, if any
+    pub fn align(&self) -> Option<u32> {
+	self.align
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Hash, Debug, Default)]
+/// Struct declaration
+pub struct Struct<'a> {
+    name: &'a str,
+    fields_ptr: usize, // *const Field<'a>
+    num_fields: usize,
+    cfg: Option<Cfg<'a>>
+}
+
+impl<'a> Struct<'a> {
+    /// Create new struct
+    ///
+    /// name - name of struct
+    ///
+    /// fields - members of struct
+    ///
+    /// cfg - conditional-compilation guard, if any
+    pub fn new(
+	name: &'a str,
+	fields: &'a [Field<'a>],
+	cfg: Option<Cfg<'a>>
+    ) -> Self {
+	let fields_ptr = fields.as_ptr() as usize;
+	Self {
+	    name,
+	    fields_ptr,
+	    cfg,
+	    num_fields: fields.len()
+	}
+    }
+
+    /// name of struct
+    pub fn name(&self) -> &'a str {
+	self.name
+    }
+
+    /// members of struct
+    pub fn fields(&self) -> &'a [Field<'a>] {
+	unsafe {
+	    core::slice::from_raw_parts(self.fields_ptr as *const Field<'a>, self.num_fields)
+	}
+    }
+
+    /// conditional-compilation guard
+    pub fn cfg(&self) -> Option<Cfg<'a>> {
+	self.cfg
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Hash, Debug, Default)]
+/// Union declaration
+pub struct Union<'a> {
+    name: &'a str,
+    fields_ptr: usize, // *const Field<'a>
+    num_fields: usize,
+    cfg: Option<Cfg<'a>>
+}
+
+impl<'a> Union<'a> {
+    /// Create new union
+    ///
+    /// name - name of union
+    ///
+    /// fields - members of union
+    ///
+    /// cfg - conditional-compilation guard, if any
+    pub fn new(
+	name: &'a str,
+	fields: &'a [Field<'a>],
+	cfg: Option<Cfg<'a>>
+    ) -> Self {
+	let fields_ptr = fields.as_ptr() as usize;
+	Self {
+	    name,
+	    fields_ptr,
+	    cfg,
+	    num_fields: fields.len()
+	}
+    }
+
+    /// name of union
+    pub fn name(&self) -> &'a str {
+	self.name
+    }
+
+    /// members of union
+    pub fn fields(&self) -> &'a [Field<'a>] {
+	unsafe {
+	    core::slice::from_raw_parts(self.fields_ptr as *const Field<'a>, self.num_fields)
+	}
+    }
+
+    /// conditional-compilation guard
+    pub fn cfg(&self) -> Option<Cfg<'a>> {
+	self.cfg
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Hash, Debug, Default)]
+/// Enum declaration
+pub struct Enum<'a> {
+    name: &'a str,
+    variants_ptr: usize, // *const (&'a str, Option<&'a str>)
+    num_variants: usize,
+    cfg: Option<Cfg<'a>>
+}
+
+impl<'a> Enum<'a> {
+    /// Create new enum
+    ///
+    /// name - name of enum
+    ///
+    /// variants - (variant, value) pairs; value renders `NAME = value` when present
+    ///
+    /// cfg - conditional-compilation guard, if any
+    pub fn new(
+	name: &'a str,
+	variants: &'a [(&'a str, Option<&'a str>)],
+	cfg: Option<Cfg<'a>>
+    ) -> Self {
+	let variants_ptr = variants.as_ptr() as usize;
+	Self {
+	    name,
+	    variants_ptr,
+	    cfg,
+	    num_variants: variants.len()
+	}
+    }
+
+    /// name of enum
+    pub fn name(&self) -> &'a str {
+	self.name
+    }
+
+    /// (variant, value) pairs
+    pub fn variants(&self) -> &'a [(&'a str, Option<&'a str>)] {
+	unsafe {
+	    core::slice::from_raw_parts(self.variants_ptr as *const (&'a str, Option<&'a str>), self.num_variants)
+	}
+    }
+
+    /// conditional-compilation guard
+    pub fn cfg(&self) -> Option<Cfg<'a>> {
+	self.cfg
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+/// C++ member access
+pub enum Access {
+    #[default]
+    /// visible to everyone
+    Public,
+    /// visible only to the class and its friends
+    Private,
+    /// visible to the class, its friends, and derived classes
+    Protected
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Hash, Debug, Default)]
+/// C++ class method
+pub struct Method<'a> {
+    access: Access,
+    func: Func<'a>,
+    r#static: bool,
+    r#virtual: bool,
+    r#const: bool
+}
+
+impl<'a> Method<'a> {
+    /// Create new method
+    ///
+    /// access - visibility of method
+    ///
+    /// func - signature of method
+    ///
+    /// static - whether method is `static`
+    ///
+    /// virtual - whether method is `virtual`
+    ///
+    /// const - whether method is `const`
+    pub fn new(
+	access: Access,
+	func: Func<'a>,
+	r#static: bool,
+	r#virtual: bool,
+	r#const: bool
+    ) -> Self {
+	Self {
+	    access,
+	    func,
+	    r#static,
+	    r#virtual,
+	    r#const
+	}
+    }
+
+    /// visibility of method
+    pub fn access(&self) -> Access {
+	self.access
+    }
+
+    /// signature of method
+    pub fn func(&self) -> Func<'a> {
+	self.func
+    }
+
+    /// whether method is `static`
+    pub fn r#static(&self) -> bool {
+	self.r#static
+    }
+
+    /// whether method is `virtual`
+    pub fn r#virtual(&self) -> bool {
+	self.r#virtual
+    }
+
+    /// whether method is `const`
+    pub fn r#const(&self) -> bool {
+	self.r#const
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Hash, Debug, Default)]
+/// C++ class declaration
+pub struct Class<'a> {
+    name: &'a str,
+    methods_ptr: usize, // *const Method<'a>
+    num_methods: usize,
+    fields_ptr: usize, // *const (Access, Field<'a>)
+    num_fields: usize,
+    cfg: Option<Cfg<'a>>
+}
+
+impl<'a> Class<'a> {
+    /// Create new class
+    ///
+    /// name - name of class
+    ///
+    /// methods - methods of class, in declaration order; multiple methods
+    /// may share a name (overloading)
+    ///
+    /// fields - access-labeled fields of class
+    ///
+    /// cfg - conditional-compilation guard, if any
+    pub fn new(
+	name: &'a str,
+	methods: &'a [Method<'a>],
+	fields: &'a [(Access, Field<'a>)],
+	cfg: Option<Cfg<'a>>
+    ) -> Self {
+	let methods_ptr = methods.as_ptr() as usize;
+	let fields_ptr = fields.as_ptr() as usize;
+	Self {
+	    name,
+	    methods_ptr,
+	    fields_ptr,
+	    cfg,
+	    num_methods: methods.len(),
+	    num_fields: fields.len()
+	}
+    }
+
+    /// name of class
+    pub fn name(&self) -> &'a str {
+	self.name
+    }
+
+    /// methods of class
+    pub fn methods(&self) -> &'a [Method<'a>] {
+	unsafe {
+	    core::slice::from_raw_parts(self.methods_ptr as *const Method<'a>, self.num_methods)
+	}
+    }
+
+    /// access-labeled fields of class
+    pub fn fields(&self) -> &'a [(Access, Field<'a>)] {
+	unsafe {
+	    core::slice::from_raw_parts(self.fields_ptr as *const (Access, Field<'a>), self.num_fields)
+	}
+    }
+
+    /// conditional-compilation guard
+    pub fn cfg(&self) -> Option<Cfg<'a>> {
+	self.cfg
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, Hash, Debug, Default)]
+/// C++ namespace
+pub struct Namespace<'a> {
+    path: &'a str, // "a::b::c"
+    namespaces_ptr: usize, // *const Namespace<'a>
+    num_namespaces: usize,
+    classes_ptr: usize, // *const Class<'a>
+    num_classes: usize,
+    funcs_ptr: usize, // *const Func<'a>
+    num_funcs: usize,
+    types_ptr: usize, // *const Type<'a>
+    num_types: usize,
+    cfg: Option<Cfg<'a>>
+}
+
+impl<'a> Namespace<'a> {
+    /// Create new namespace
+    ///
+    /// path - namespace path, e.g. `a::b::c`
+    ///
+    /// namespaces - nested namespaces
+    ///
+    /// classes - classes declared directly in this namespace
+    ///
+    /// funcs - functions declared directly in this namespace; multiple
+    /// functions may share a name (overloading)
+    ///
+    /// types - typedefs declared directly in this namespace
+    ///
+    /// cfg - conditional-compilation guard, if any
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+	path: &'a str,
+	namespaces: &'a [Namespace<'a>],
+	classes: &'a [Class<'a>],
+	funcs: &'a [Func<'a>],
+	types: &'a [Type<'a>],
+	cfg: Option<Cfg<'a>>
+    ) -> Self {
+	let namespaces_ptr = namespaces.as_ptr() as usize;
+	let classes_ptr = classes.as_ptr() as usize;
+	let funcs_ptr = funcs.as_ptr() as usize;
+	let types_ptr = types.as_ptr() as usize;
+	Self {
+	    path,
+	    namespaces_ptr,
+	    classes_ptr,
+	    funcs_ptr,
+	    types_ptr,
+	    cfg,
+	    num_namespaces: namespaces.len(),
+	    num_classes: classes.len(),
+	    num_funcs: funcs.len(),
+	    num_types: types.len()
+	}
+    }
+
+    /// namespace path, e.g. `a::b::c`
+    pub fn path(&self) -> &'a str {
+	self.path
+    }
+
+    /// nested namespaces
+    pub fn namespaces(&self) -> &'a [Namespace<'a>] {
+	unsafe {
+	    core::slice::from_raw_parts(self.namespaces_ptr as *const Namespace<'a>, self.num_namespaces)
+	}
+    }
+
+    /// classes declared directly in this namespace
+    pub fn classes(&self) -> &'a [Class<'a>] {
+	unsafe {
+	    core::slice::from_raw_parts(self.classes_ptr as *const Class<'a>, self.num_classes)
+	}
+    }
+
+    /// functions declared directly in this namespace
+    pub fn funcs(&self) -> &'a [Func<'a>] {
+	unsafe {
+	    core::slice::from_raw_parts(self.funcs_ptr as *const Func<'a>, self.num_funcs)
+	}
+    }
+
+    /// typedefs declared directly in this namespace
+    pub fn types(&self) -> &'a [Type<'a>] {
+	unsafe {
+	    core::slice::from_raw_parts(self.types_ptr as *const Type<'a>, self.num_types)
+	}
+    }
+
+    /// conditional-compilation guard
+    pub fn cfg(&self) -> Option<Cfg<'a>> {
+	self.cfg
+    }
 }
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -269,15 +836,91 @@ impl<'a> HeaderGuard<'a> {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+/// function parameter
+pub struct Param<'a> {
+    r#type: &'a str,
+    name: Option<&'a str>,
+    pointer: bool,
+    r#const: bool,
+    restrict: bool,
+    volatile: bool
+}
+
+impl<'a> Param<'a> {
+    /// Create new parameter
+    ///
+    /// type - base type of parameter
+    ///
+    /// name - name of parameter, if any
+    ///
+    /// pointer - whether parameter is a pointer
+    ///
+    /// const - whether the pointee is const-qualified
+    ///
+    /// restrict - whether the pointer is restrict-qualified
+    ///
+    /// volatile - whether the pointer is volatile-qualified
+    pub fn new(
+	r#type: &'a str,
+	name: Option<&'a str>,
+	pointer: bool,
+	r#const: bool,
+	restrict: bool,
+	volatile: bool
+    ) -> Self {
+	Self {
+	    r#type,
+	    name,
+	    pointer,
+	    r#const,
+	    restrict,
+	    volatile
+	}
+    }
+
+    /// base type of parameter
+    pub fn r#type(&self) -> &'a str {
+	self.r#type
+    }
+
+    /// name of parameter, if any
+    pub fn name(&self) -> Option<&'a str> {
+	self.name
+    }
+
+    /// whether parameter is a pointer
+    pub fn pointer(&self) -> bool {
+	self.pointer
+    }
+
+    /// whether the pointee is const-qualified
+    pub fn r#const(&self) -> bool {
+	self.r#const
+    }
+
+    /// whether the pointer is restrict-qualified
+    pub fn restrict(&self) -> bool {
+	self.restrict
+    }
+
+    /// whether the pointer is volatile-qualified
+    pub fn volatile(&self) -> bool {
+	self.volatile
+    }
+}
+
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Clone, Copy, Hash, Debug, Default)]
 /// Function
 pub struct Func<'a> {
     out: &'a str,
     name: &'a str,
-    params_ptr: usize, // *const &'a str
+    params_ptr: usize, // *const Param<'a>
     num_params: usize,
-    va: Variadic
+    va: Variadic,
+    cfg: Option<Cfg<'a>>
 }
 
 impl<'a> Func<'a> {
@@ -290,17 +933,21 @@ impl<'a> Func<'a> {
     /// params - parameters of function
     ///
     /// va - arity of function
+    ///
+    /// cfg - conditional-compilation guard, if any
     pub fn new(
 	out: &'a str,
 	name: &'a str,
-	params: &'a[&'a str],
-	va: Variadic
+	params: &'a [Param<'a>],
+	va: Variadic,
+	cfg: Option<Cfg<'a>>
     ) -> Self {
 	let params_ptr = params.as_ptr() as usize;
 	Self {
 	    out,
 	    name,
 	    va,
+	    cfg,
 	    params_ptr,
 	    num_params: params.len()
 	}
@@ -317,9 +964,9 @@ impl<'a> Func<'a> {
     }
 
     /// parameters of  function
-    pub fn params(&self) -> &'a [&'a str] {
+    pub fn params(&self) -> &'a [Param<'a>] {
 	unsafe {
-	    core::slice::from_raw_parts(self.params_ptr as *const &'a str, self.num_params)
+	    core::slice::from_raw_parts(self.params_ptr as *const Param<'a>, self.num_params)
 	}
     }
 
@@ -327,6 +974,11 @@ impl<'a> Func<'a> {
     pub fn va(&self) -> Variadic {
 	self.va
     }
+
+    /// conditional-compilation guard
+    pub fn cfg(&self) -> Option<Cfg<'a>> {
+	self.cfg
+    }
 }
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -335,21 +987,26 @@ impl<'a> Func<'a> {
 pub struct Macro<'a> {
     tok: &'a str,
     val: &'a str,
+    cfg: Option<Cfg<'a>>
 }
 
 impl<'a> Macro<'a> {
     /// Create new macro
     ///
     /// tok - macro token (contains parameters if function macro)
-    /// 
+    ///
     /// val - value of token
+    ///
+    /// cfg - conditional-compilation guard, if any
     pub fn new(
 	tok: &'a str,
-	val: &'a str
+	val: &'a str,
+	cfg: Option<Cfg<'a>>
     ) -> Self {
 	Self {
 	    tok,
-	    val
+	    val,
+	    cfg
 	}
     }
 
@@ -362,4 +1019,9 @@ impl<'a> Macro<'a> {
     pub fn val(&self) -> &'a str {
 	self.val
     }
+
+    /// conditional-compilation guard
+    pub fn cfg(&self) -> Option<Cfg<'a>> {
+	self.cfg
+    }
 }
@@ -32,6 +32,8 @@ OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 */
 
 use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::boxed::Box;
 use core::fmt::Write;
 
 use crate::{
@@ -39,11 +41,25 @@ use crate::{
     Macro,
     Type,
     Func,
+    Param,
     HeaderGuard,
     CXX,
-    Variadic
+    Variadic,
+    Cfg,
+    Field,
+    Struct,
+    Union,
+    Enum,
+    Access,
+    Method,
+    Class,
+    Namespace
 };
 
+mod expr;
+pub mod parse;
+mod sort;
+
 /// Create C tokens from item
 pub trait Token {
     fn token(&self) -> String;
@@ -199,42 +215,167 @@ impl EndToken for CXX {
     }
 }
 
-impl Token for Header<'_> {
-    fn token(&self) -> String {
-	let mut out = String::new();
-	if let Some(guard) =  &self.guard {
-	    out.push_str(&guard.token());
-	    out.push('\n');
+/// emit a slice of `Cfg`-guarded items, coalescing adjacent items that
+/// share an identical `Cfg` into a single `#if … / #endif` block
+fn emit_cfg_section<T>(
+    out: &mut String,
+    items: &[T],
+    cfg_of: impl Fn(&T) -> Option<Cfg<'_>>,
+    token_of: impl Fn(&T) -> String
+) {
+    let mut i = 0;
+    while i < items.len() {
+	let cfg = cfg_of(&items[i]);
+	let mut j = i + 1;
+	while j < items.len() && cfg_of(&items[j]) == cfg {
+	    j += 1;
 	}
-	out.push_str(&self.cxx.token());
-	out.push('\n');
-	for i in 0..self.num_types {
-	    out.push_str(&self.types()[i].token());
+	if let Some(c) = cfg {
+	    out.push_str(&c.token());
 	}
-	out.push('\n');
-	for i in 0..self.num_macros {
-	    out.push_str(&self.macros()[i].token());
-	}
-	out.push('\n');
-	for i in 0..self.num_funcs {
-	    out.push_str(&self.funcs()[i].token());
+	for item in &items[i..j] {
+	    out.push_str(&token_of(item));
 	}
-	out.push('\n');
-	if let Some(extra) = self.extra {
-	    out.push_str(extra);
-	    out.push('\n');
+	if let Some(c) = cfg {
+	    out.push_str(&c.end_token());
 	}
-	out.push_str(&self.cxx.end_token());
+	i = j;
+    }
+}
+
+impl Token for Cfg<'_> {
+    fn token(&self) -> String {
+	let mut out = String::from("#if ");
+	out.push_str(&self.expr_str());
 	out.push('\n');
-	if let Some(guard) = &self.guard {
-	    out.push_str(&guard.end_token());
-	    out.push('\n');
+	out
+    }
+}
+
+impl EndToken for Cfg<'_> {
+    fn end_token(&self) -> String {
+	String::from("#endif\n")
+    }
+}
+
+impl Cfg<'_> {
+    fn expr_str(&self) -> String {
+	match self {
+	    Cfg::Defined(tok) => {
+		let mut out = String::from("defined(");
+		out.push_str(tok);
+		out.push(')');
+		out
+	    },
+	    Cfg::NotDefined(tok) => {
+		let mut out = String::from("!defined(");
+		out.push_str(tok);
+		out.push(')');
+		out
+	    },
+	    Cfg::Eq(tok, val) => {
+		let mut out = String::from("(");
+		out.push_str(tok);
+		out.push_str(") == (");
+		out.push_str(val);
+		out.push(')');
+		out
+	    },
+	    Cfg::Compare { tok, op, val } => {
+		let mut out = String::from("(");
+		out.push_str(tok);
+		out.push_str(") ");
+		out.push_str(op);
+		out.push_str(" (");
+		out.push_str(val);
+		out.push(')');
+		out
+	    },
+	    Cfg::All(cfgs) => join_cfgs(cfgs, "&&"),
+	    Cfg::Any(cfgs) => join_cfgs(cfgs, "||"),
+	    Cfg::Not(cfg) => {
+		let mut out = String::from("!(");
+		out.push_str(&cfg.expr_str());
+		out.push(')');
+		out
+	    }
 	}
-	if let Some(post_extra) = &self.post_extra {
-	    out.push_str(post_extra);
-	    out.push('\n');
+    }
+}
+
+fn join_cfgs(cfgs: &[Cfg<'_>], op: &str) -> String {
+    let mut out = String::from("(");
+    for (i, cfg) in cfgs.iter().enumerate() {
+	if i != 0 {
+	    out.push(' ');
+	    out.push_str(op);
+	    out.push(' ');
 	}
-	out
+	out.push_str(&cfg.expr_str());
+    }
+    out.push(')');
+    out
+}
+
+/// assemble a full header around a pre-rendered block of typedef/struct/
+/// union/enum declarations, shared by `Token::token` and `Header::sorted_token`
+fn header_wrapper(header: &Header<'_>, decls: &str) -> String {
+    let mut out = String::new();
+    if let Some(guard) = &header.guard {
+	out.push_str(&guard.token());
+	out.push('\n');
+    }
+    out.push_str(&header.cxx.token());
+    out.push('\n');
+    out.push_str(decls);
+    out.push('\n');
+    emit_cfg_section(&mut out, header.macros(), |m| m.cfg(), |m| m.token());
+    out.push('\n');
+    emit_cfg_section(&mut out, header.funcs(), |f| f.cfg(), |f| f.token());
+    out.push('\n');
+    emit_cfg_section(&mut out, header.classes(), |c| c.cfg(), |c| c.token());
+    emit_cfg_section(&mut out, header.namespaces(), |n| n.cfg(), |n| n.token());
+    out.push('\n');
+    if let Some(extra) = header.extra {
+	out.push_str(extra);
+	out.push('\n');
+    }
+    out.push_str(&header.cxx.end_token());
+    out.push('\n');
+    if let Some(guard) = &header.guard {
+	out.push_str(&guard.end_token());
+	out.push('\n');
+    }
+    if let Some(post_extra) = &header.post_extra {
+	out.push_str(post_extra);
+	out.push('\n');
+    }
+    out
+}
+
+impl Token for Header<'_> {
+    fn token(&self) -> String {
+	let mut decls = String::new();
+	emit_cfg_section(&mut decls, self.types(), |t| t.cfg(), |t| t.token());
+	emit_cfg_section(&mut decls, self.structs(), |s| s.cfg(), |s| s.token());
+	emit_cfg_section(&mut decls, self.unions(), |u| u.cfg(), |u| u.token());
+	emit_cfg_section(&mut decls, self.enums(), |e| e.cfg(), |e| e.token());
+	header_wrapper(self, &decls)
+    }
+}
+
+impl Header<'_> {
+    /// dependency-ordering-aware variant of `token`
+    ///
+    /// typedefs, structs, unions, and enums are emitted in topological
+    /// order - by whole-word references in their type text - instead of
+    /// raw array order, falling back to a forward declaration to break any
+    /// dependency cycle. Declarations are visited in their original order,
+    /// and each is preceded only by the not-yet-emitted declarations it
+    /// references, so already-ordered input keeps its order. `token` itself
+    /// still emits declarations in raw array order.
+    pub fn sorted_token(&self) -> String {
+	header_wrapper(self, &sort::sorted_decls(self))
     }
 }
 
@@ -257,36 +398,109 @@ impl EndToken for HeaderGuard<'_> {
     }
 }
 
+impl Token for Param<'_> {
+    fn token(&self) -> String {
+	let mut out = String::new();
+	if self.r#const() {
+	    out.push_str("const ");
+	}
+	out.push_str(self.r#type());
+	if self.pointer() {
+	    out.push_str(" *");
+	    if self.restrict() {
+		out.push_str(" restrict");
+	    }
+	    if self.volatile() {
+		out.push_str(" volatile");
+	    }
+	}
+	if let Some(name) = self.name() {
+	    out.push(' ');
+	    out.push_str(name);
+	}
+	out
+    }
+}
+
+/// render a function's parameter list, including a trailing `...` for
+/// variadic functions; shared by `Token for Func` and `Func::definition_stub`
+fn func_params(func: &Func<'_>) -> String {
+    let mut out = String::new();
+    for (i, param) in func.params().iter().enumerate() {
+	if i != 0 {
+	    out.push_str(", ");
+	}
+	out.push_str(&param.token());
+    }
+    if let Variadic::Variadic = func.va() {
+	if func.params().is_empty() {
+	    out.push_str("...");
+	} else {
+	    out.push_str(", ...");
+	}
+    }
+    out
+}
+
 impl Token for Func<'_> {
     fn token(&self) -> String {
 	let mut out = String::from(self.out);
 	out.push(' ');
 	out.push_str(self.name);
 	out.push('(');
-	for i in 0..self.num_params {
-	    if i != 0 {
-		out.push_str(", ");
-	    }
-	    out.push_str(self.params()[i]);
-	}
-	if let Variadic::Variadic = self.va {
-	    if self.num_params == 0 {
-		out.push_str("...");
-	    } else {
-		out.push_str(", ...");
-	    }
-	}
+	out.push_str(&func_params(self));
 	out.push_str(");\n");
 	out
     }
 }
 
+impl<'a> Func<'a> {
+    /// Create new function from bare parameter type strings, with no name
+    /// or qualifiers attached to any parameter; kept for callers that don't
+    /// need `Param`'s extra detail
+    pub fn from_strs(
+	out: &'a str,
+	name: &'a str,
+	params: &'a [&'a str],
+	va: Variadic,
+	cfg: Option<Cfg<'a>>
+    ) -> Self {
+	let params: Vec<Param<'a>> = params.iter().map(|p| Param::new(p, None, false, false, false, false)).collect();
+	Func::new(out, name, Box::leak(params.into_boxed_slice()), va, cfg)
+    }
+
+    /// render a `.c` definition skeleton with a `/* TODO */` body, for
+    /// generating a matching source file alongside the header
+    pub fn definition_stub(&self) -> String {
+	let mut out = String::from(self.out);
+	out.push(' ');
+	out.push_str(self.name);
+	out.push('(');
+	out.push_str(&func_params(self));
+	out.push_str(") {\n\t/* TODO */\n}\n");
+	out
+    }
+}
+
+impl Macro<'_> {
+    /// fold the macro's value into a constant, if it is one
+    ///
+    /// returns `None` when `val` references another identifier, divides or
+    /// modulos by zero, or does not parse as a C constant expression at all
+    pub fn eval(&self) -> Option<i128> {
+	expr::parse(self.val())?.eval().map(|v| v.value)
+    }
+}
+
 impl Token for Macro<'_> {
     fn token(&self) -> String {
 	let mut out = String::from("#define ");
 	out.push_str(self.tok);
 	out.push(' ');
-	out.push_str(self.val);
+	match expr::parse(self.val()).and_then(|e| e.eval()) {
+	    Some(v) => out.push_str(&v.token()),
+	    None => out.push_str(self.val)
+	}
 	out.push('\n');
 	out
     }
@@ -303,6 +517,195 @@ impl Token for Type<'_> {
     }
 }
 
+impl Token for Field<'_> {
+    fn token(&self) -> String {
+	let mut out = String::from(self.r#type);
+	out.push(' ');
+	out.push_str(self.name);
+	if let Some(width) = self.bitfield {
+	    out.push_str(" : ");
+	    out.push_str(&width.token());
+	}
+	if self.packed || self.align.is_some() {
+	    out.push_str(" __attribute__((");
+	    if self.packed {
+		out.push_str("packed");
+		if self.align.is_some() {
+		    out.push_str(", ");
+		}
+	    }
+	    if let Some(align) = self.align {
+		out.push_str("aligned(");
+		out.push_str(&align.token());
+		out.push(')');
+	    }
+	    out.push_str("))");
+	}
+	out.push_str("; ");
+	out
+    }
+}
+
+fn fields_token(out: &mut String, tag: &str, name: &str, fields: &[Field<'_>]) {
+    out.push_str(tag);
+    out.push(' ');
+    out.push_str(name);
+    out.push_str(" { ");
+    for field in fields {
+	out.push_str(&field.token());
+    }
+    out.push_str("};\n");
+}
+
+/// emit the `typedef <tag> { … } name;` idiom, using `name` only as the
+/// typedef alias rather than a struct/union tag
+fn anon_typedef_token(tag: &str, name: &str, fields: &[Field<'_>]) -> String {
+    let mut out = String::from("typedef ");
+    out.push_str(tag);
+    out.push_str(" { ");
+    for field in fields {
+	out.push_str(&field.token());
+    }
+    out.push_str("} ");
+    out.push_str(name);
+    out.push_str(";\n");
+    out
+}
+
+impl Token for Struct<'_> {
+    fn token(&self) -> String {
+	let mut out = String::new();
+	fields_token(&mut out, "struct", self.name, self.fields());
+	out
+    }
+}
+
+impl Struct<'_> {
+    /// render the `typedef struct { … } Name;` idiom, with `name` used only
+    /// as the typedef alias
+    pub fn typedef_token(&self) -> String {
+	anon_typedef_token("struct", self.name, self.fields())
+    }
+}
+
+impl Token for Union<'_> {
+    fn token(&self) -> String {
+	let mut out = String::new();
+	fields_token(&mut out, "union", self.name, self.fields());
+	out
+    }
+}
+
+impl Union<'_> {
+    /// render the `typedef union { … } Name;` idiom, with `name` used only
+    /// as the typedef alias
+    pub fn typedef_token(&self) -> String {
+	anon_typedef_token("union", self.name, self.fields())
+    }
+}
+
+impl Token for Enum<'_> {
+    fn token(&self) -> String {
+	let mut out = String::from("enum ");
+	out.push_str(self.name);
+	out.push_str(" { ");
+	for (i, (variant, value)) in self.variants().iter().enumerate() {
+	    if i != 0 {
+		out.push_str(", ");
+	    }
+	    out.push_str(variant);
+	    if let Some(value) = value {
+		out.push_str(" = ");
+		out.push_str(value);
+	    }
+	}
+	out.push_str(" };\n");
+	out
+    }
+}
+
+/// render an access-labeled section of a class body, emitting an
+/// `access: ` label whenever it changes from the previous item
+fn emit_access_section<T>(
+    out: &mut String,
+    items: &[T],
+    access_of: impl Fn(&T) -> Access,
+    token_of: impl Fn(&T) -> String
+) {
+    let mut current = None;
+    for item in items {
+	let access = access_of(item);
+	if current != Some(access) {
+	    out.push_str(access_label(access));
+	    out.push_str(": ");
+	    current = Some(access);
+	}
+	out.push_str(&token_of(item));
+    }
+}
+
+fn access_label(access: Access) -> &'static str {
+    match access {
+	Access::Public => "public",
+	Access::Private => "private",
+	Access::Protected => "protected"
+    }
+}
+
+fn method_token(method: &Method<'_>) -> String {
+    let func = method.func();
+    let mut out = String::new();
+    if method.r#static() {
+	out.push_str("static ");
+    }
+    if method.r#virtual() {
+	out.push_str("virtual ");
+    }
+    out.push_str(func.out());
+    out.push(' ');
+    out.push_str(func.name());
+    out.push('(');
+    out.push_str(&func_params(&func));
+    out.push(')');
+    if method.r#const() {
+	out.push_str(" const");
+    }
+    out.push_str("; ");
+    out
+}
+
+impl Token for Class<'_> {
+    fn token(&self) -> String {
+	let mut out = String::from("class ");
+	out.push_str(self.name());
+	out.push_str(" { ");
+	emit_access_section(&mut out, self.methods(), |m| m.access(), method_token);
+	emit_access_section(&mut out, self.fields(), |(access, _)| *access, |(_, field)| field.token());
+	out.push_str("};\n");
+	out
+    }
+}
+
+impl Token for Namespace<'_> {
+    fn token(&self) -> String {
+	let mut out = String::new();
+	for seg in self.path().split("::") {
+	    out.push_str("namespace ");
+	    out.push_str(seg);
+	    out.push_str(" { ");
+	}
+	emit_cfg_section(&mut out, self.types(), |t| t.cfg(), |t| t.token());
+	emit_cfg_section(&mut out, self.classes(), |c| c.cfg(), |c| c.token());
+	emit_cfg_section(&mut out, self.funcs(), |f| f.cfg(), |f| f.token());
+	emit_cfg_section(&mut out, self.namespaces(), |n| n.cfg(), |n| n.token());
+	for _ in self.path().split("::") {
+	    out.push_str("} ");
+	}
+	out.push('\n');
+	out
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::{
@@ -310,9 +713,19 @@ mod test {
 	    Macro,
 	    Type,
 	    Func,
+	    Param,
 	    Variadic,
 	    HeaderGuard,
 	    CXX,
+	    Cfg,
+	    Field,
+	    Struct,
+	    Union,
+	    Enum,
+	    Access,
+	    Method,
+	    Class,
+	    Namespace,
 	    Header
 	},
 	Token,
@@ -322,7 +735,8 @@ mod test {
     fn r#macro() {
 	let m = Macro::new(
 	    "H",
-	    "1"
+	    "1",
+	    None
 	).token();
 	assert_eq!(&m, "#define H 1\n");
     }
@@ -331,32 +745,36 @@ mod test {
     fn r#type() {
 	let t = Type::new(
 	    "size_t",
-	    "unsigned long"
+	    "unsigned long",
+	    None
 	).token();
 	assert_eq!(&t, "typedef unsigned long size_t;\n");
     }
 
     #[test]
     fn func() {
-	let f1=  Func::new(
+	let f1=  Func::from_strs(
 	    "int",
 	    "printf",
 	    &["const char*"],
-	    Variadic::Variadic
+	    Variadic::Variadic,
+	    None
 	).token();
 	let f2 = Func::new(
 	    "void",
 	    "q",
 	    &[],
-	    Variadic::Variadic
+	    Variadic::Variadic,
+	    None
 	).token();
 	let f3 = Func::new(
 	    "void",
 	    "q",
 	    &[],
-	    Variadic::Nary
+	    Variadic::Nary,
+	    None
 	).token();
-	let f4 = Func::new(
+	let f4 = Func::from_strs(
 	    "void",
 	    "q",
 	    &[
@@ -387,7 +805,8 @@ mod test {
 		"y",
 		"z"
 	    ],
-	    Variadic::Nary
+	    Variadic::Nary,
+	    None
 	).token();
 	assert_eq!(&f1, "int printf(const char*, ...);\n");
 	assert_eq!(&f2, "void q(...);\n");
@@ -395,6 +814,29 @@ mod test {
 	assert_eq!(&f4, "void q(a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p, q, r, s, t, u, v, w, x, y, z);\n");
     }
 
+    #[test]
+    fn param() {
+	let plain = Param::new("int", Some("x"), false, false, false, false).token();
+	let ptr = Param::new("char", Some("s"), true, false, false, false).token();
+	let ptr_const_restrict = Param::new("char", Some("s"), true, true, true, false).token();
+	let ptr_volatile_unnamed = Param::new("int", None, true, false, false, true).token();
+	assert_eq!(&plain, "int x");
+	assert_eq!(&ptr, "char * s");
+	assert_eq!(&ptr_const_restrict, "const char * restrict s");
+	assert_eq!(&ptr_volatile_unnamed, "int * volatile");
+    }
+
+    #[test]
+    fn definition_stub() {
+	let params = [
+	    Param::new("char", Some("s"), true, true, true, false),
+	    Param::new("int", Some("n"), false, false, false, false)
+	];
+	let f = Func::new("int", "copy_n", &params, Variadic::Nary, None);
+	assert_eq!(&f.token(), "int copy_n(const char * restrict s, int n);\n");
+	assert_eq!(&f.definition_stub(), "int copy_n(const char * restrict s, int n) {\n\t/* TODO */\n}\n");
+    }
+
     #[test]
     fn header_guard() {
 	let h1 = HeaderGuard::new("a", "1").token();
@@ -418,21 +860,64 @@ mod test {
 	assert_eq!(&cxx_only.end_token(), "");
     }
 
+    #[test]
+    fn cfg() {
+	let defined = Cfg::Defined("FOO").token();
+	let not_defined = Cfg::NotDefined("FOO").end_token();
+	assert_eq!(&defined, "#if defined(FOO)\n");
+	assert_eq!(&not_defined, "#endif\n");
+
+	let want_ext1 = Cfg::Defined("__STDC_WANT_LIB_EXT1__");
+	let gated = Cfg::All(&[want_ext1, Cfg::Eq("__STDC_WANT_LIB_EXT1__", "1")]).token();
+	assert_eq!(&gated, "#if (defined(__STDC_WANT_LIB_EXT1__) && (__STDC_WANT_LIB_EXT1__) == (1))\n");
+    }
+
+    #[test]
+    fn header_cfg() {
+	let ext1 = Cfg::Defined("__STDC_WANT_LIB_EXT1__");
+	let f1 = Func::from_strs("errno_t", "strcpy_s", &["char*", "rsize_t", "const char*"], Variadic::Nary, Some(ext1));
+	let f2 = Func::from_strs("errno_t", "strcat_s", &["char*", "rsize_t", "const char*"], Variadic::Nary, Some(ext1));
+	let f3 = Func::from_strs("char*", "strcpy", &["char*", "const char*"], Variadic::Nary, None);
+	let h = Header::new(
+	    None,
+	    "string.h",
+	    None,
+	    &[f3, f1, f2],
+	    &[],
+	    &[],
+	    &[],
+	    &[],
+	    &[],
+	    &[],
+	    &[],
+	    CXX::CXX,
+	    None,
+	    None
+	).token();
+	assert_eq!(
+	    &h,
+	    "#ifdef __cplusplus\nextern \"C\" {\n#endif\n\n\n\nchar* strcpy(char*, const char*);\n#if defined(__STDC_WANT_LIB_EXT1__)\nerrno_t strcpy_s(char*, rsize_t, const char*);\nerrno_t strcat_s(char*, rsize_t, const char*);\n#endif\n\n\n#ifdef __cplusplus\n}\n#endif\n\n"
+	);
+    }
+
     #[test]
     fn header() {
-	let f1=  Func::new(
+	let f1=  Func::from_strs(
 	    "int",
 	    "printf",
 	    &["const char*"],
-	    Variadic::Variadic
+	    Variadic::Variadic,
+	    None
 	);
 	let t = Type::new(
 	    "size_t",
-	    "unsigned long"
+	    "unsigned long",
+	    None
 	);
 	let m = Macro::new(
 	    "H",
-	    "1"
+	    "1",
+	    None
 	);
 	let h = Header::new(
 	    None,
@@ -441,11 +926,167 @@ mod test {
 	    &[f1],
 	    &[m],
 	    &[t],
+	    &[],
+	    &[],
+	    &[],
+	    &[],
+	    &[],
+	    CXX::CXX,
+	    None,
+	    None
+	).token();
+	assert_eq!(&h, "#ifdef __cplusplus\nextern \"C\" {\n#endif\n\ntypedef unsigned long size_t;\n\n#define H 1\n\nint printf(const char*, ...);\n\n\n#ifdef __cplusplus\n}\n#endif\n\n");
+    }
+
+    #[test]
+    fn field() {
+	let plain = Field::new("int", "x", None, false, None).token();
+	let bitfield = Field::new("unsigned", "flag", Some(1), false, None).token();
+	let packed = Field::new("int", "x", None, true, None).token();
+	let aligned = Field::new("int", "x", None, false, Some(16)).token();
+	let both = Field::new("int", "x", None, true, Some(16)).token();
+	assert_eq!(&plain, "int x; ");
+	assert_eq!(&bitfield, "unsigned flag : 1; ");
+	assert_eq!(&packed, "int x __attribute__((packed)); ");
+	assert_eq!(&aligned, "int x __attribute__((aligned(16))); ");
+	assert_eq!(&both, "int x __attribute__((packed, aligned(16))); ");
+    }
+
+    #[test]
+    fn r#struct() {
+	let fields = [
+	    Field::new("int", "x", None, false, None),
+	    Field::new("int", "y", None, false, None)
+	];
+	let s = Struct::new("point", &fields, None);
+	assert_eq!(&s.token(), "struct point { int x; int y; };\n");
+	assert_eq!(&s.typedef_token(), "typedef struct { int x; int y; } point;\n");
+    }
+
+    #[test]
+    fn union() {
+	let fields = [
+	    Field::new("int", "i", None, false, None),
+	    Field::new("float", "f", None, false, None)
+	];
+	let u = Union::new("num", &fields, None);
+	assert_eq!(&u.token(), "union num { int i; float f; };\n");
+	assert_eq!(&u.typedef_token(), "typedef union { int i; float f; } num;\n");
+    }
+
+    #[test]
+    fn r#enum() {
+	let variants = [("RED", None), ("GREEN", Some("2")), ("BLUE", None)];
+	let e = Enum::new("color", &variants, None);
+	assert_eq!(&e.token(), "enum color { RED, GREEN = 2, BLUE };\n");
+    }
+
+    #[test]
+    fn sorted_token() {
+	let t1 = Type::new("a_t", "int", None);
+	let t2 = Type::new("b_t", "long", None);
+	let fields_a = [Field::new("struct B*", "b", None, false, None)];
+	let fields_b = [Field::new("struct A*", "a", None, false, None)];
+	let sa = Struct::new("A", &fields_a, None);
+	let sb = Struct::new("B", &fields_b, None);
+	let types = [t1, t2];
+	let structs = [sa, sb];
+	let h = Header::new(
+	    None,
+	    "test.h",
+	    None,
+	    &[],
+	    &[],
+	    &types,
+	    &structs,
+	    &[],
+	    &[],
+	    &[],
+	    &[],
+	    CXX::CXX,
+	    None,
+	    None
+	);
+	assert_eq!(
+	    &h.sorted_token(),
+	    "#ifdef __cplusplus\nextern \"C\" {\n#endif\n\ntypedef int a_t;\ntypedef long b_t;\nstruct A;\nstruct B { struct A* a; };\nstruct A { struct B* b; };\n\n\n\n\n#ifdef __cplusplus\n}\n#endif\n\n"
+	);
+    }
+
+    #[test]
+    fn sorted_token_typedef_cycle() {
+	let t = Type::new("foo_t", "struct bar", None);
+	let fields = [Field::new("foo_t*", "f", None, false, None)];
+	let s = Struct::new("bar", &fields, None);
+	let types = [t];
+	let structs = [s];
+	let h = Header::new(
+	    None,
+	    "test.h",
+	    None,
+	    &[],
+	    &[],
+	    &types,
+	    &structs,
+	    &[],
+	    &[],
+	    &[],
+	    &[],
 	    CXX::CXX,
 	    None,
 	    None
+	);
+	assert_eq!(
+	    &h.sorted_token(),
+	    "#ifdef __cplusplus\nextern \"C\" {\n#endif\n\ntypedef struct bar foo_t;\nstruct bar { foo_t* f; };\ntypedef struct bar foo_t;\n\n\n\n\n#ifdef __cplusplus\n}\n#endif\n\n"
+	);
+    }
+
+    #[test]
+    fn r#class() {
+	let methods = [
+	    Method::new(Access::Public, Func::new("int", "get", &[], Variadic::Nary, None), false, false, true),
+	    Method::new(Access::Public, Func::from_strs("void", "get", &["int"], Variadic::Nary, None), false, false, false),
+	    Method::new(Access::Private, Func::new("void", "reset", &[], Variadic::Nary, None), true, false, false)
+	];
+	let fields = [(Access::Private, Field::new("int", "value_", None, false, None))];
+	let c = Class::new("Counter", &methods, &fields, None);
+	assert_eq!(
+	    &c.token(),
+	    "class Counter { public: int get() const; void get(int); private: static void reset(); private: int value_; };\n"
+	);
+    }
+
+    #[test]
+    fn namespace() {
+	let t = Type::new("x_t", "int", None);
+	let types = [t];
+	let n = Namespace::new("a::b", &[], &[], &[], &types, None);
+	assert_eq!(&n.token(), "namespace a { namespace b { typedef int x_t;\n} } \n");
+    }
+
+    #[test]
+    fn header_cxx_only() {
+	let methods = [Method::new(Access::Public, Func::new("int", "get", &[], Variadic::Nary, None), false, false, true)];
+	let classes = [Class::new("Box", &methods, &[], None)];
+	let h = Header::new(
+	    None,
+	    "box.hpp",
+	    None,
+	    &[],
+	    &[],
+	    &[],
+	    &[],
+	    &[],
+	    &[],
+	    &classes,
+	    &[],
+	    CXX::CXXOnly,
+	    None,
+	    None
 	).token();
-	assert_eq!(&h, "#ifdef __cplusplus\nextern \"C\" {\n#endif\n\ntypedef unsigned long size_t;\n\n#define H 1\n\nint printf(const char*, ...);\n\n#ifdef __cplusplus\n}\n#endif\n\n");
+	assert!(!h.contains("extern \"C\""));
+	assert!(h.contains("class Box { public: int get() const; };\n"));
     }
 
     #[test]
@@ -0,0 +1,512 @@
+/*
+
+BSD 3-Clause License
+
+Copyright (c) 2025, Isaac Budzik
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the following conditions are met:
+
+1. Redistributions of source code must retain the above copyright notice, this
+   list of conditions and the following disclaimer.
+
+2. Redistributions in binary form must reproduce the above copyright notice,
+   this list of conditions and the following disclaimer in the documentation
+   and/or other materials provided with the distribution.
+
+3. Neither the name of the copyright holder nor the names of its
+   contributors may be used to endorse or promote products derived from
+   this software without specific prior written permission.
+
+THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+*/
+
+//! Constant-expression parsing and folding for C preprocessor expressions
+//!
+//! This is the subsystem `Macro::eval` and `Token for Macro` use to validate
+//! and simplify a macro body like `(1 << 3) | 0x4` down to a single literal,
+//! following the subset of C's `#if`/object-like macro grammar and its
+//! integer-promotion rules (widening to the largest of `int`, `long`, and
+//! `unsigned long`).
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use super::Token;
+
+/// A folded constant, tracking the integer rank it settled at
+///
+/// The ranks that can appear in a macro value are modeled: `int`, `unsigned
+/// int`, `long`, and `unsigned long`. A value is `unsigned` once any operand
+/// carries a `U` suffix or overflows signed range, and `long` once any
+/// operand carries an `L` suffix, overflows `int`, or the value became
+/// `unsigned` as the result of a binary operation (`unsigned` alone is only
+/// reachable by a lone literal passing through unchanged).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct CValue {
+    pub(crate) value: i128,
+    pub(crate) unsigned: bool,
+    pub(crate) long: bool
+}
+
+impl CValue {
+    fn int(value: i128) -> Self {
+	Self {
+	    value,
+	    unsigned: false,
+	    long: false
+	}
+    }
+
+    fn promote(a: Self, b: Self) -> (i128, i128, bool, bool) {
+	let unsigned = a.unsigned || b.unsigned;
+	let long = a.long || b.long || unsigned;
+	(a.value, b.value, unsigned, long)
+    }
+
+    /// render as the literal this subsystem would fold a macro body to,
+    /// reusing the existing integer `Token` impls for suffix formatting
+    /// where one fits (the `u32` `Token` impl carries no `U` suffix, so the
+    /// `unsigned int` rank is formatted directly)
+    pub(crate) fn token(&self) -> String {
+	if self.unsigned && self.long {
+	    (self.value as u64).token()
+	} else if self.unsigned {
+	    let mut s = String::new();
+	    let _ = write!(s, "{}U", self.value as u32);
+	    s
+	} else if self.long {
+	    (self.value as i64).token()
+	} else {
+	    (self.value as i32).token()
+	}
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum UnOp {
+    Plus,
+    Neg,
+    BitNot,
+    Not
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum BinOp {
+    Mul,
+    Div,
+    Rem,
+    Add,
+    Sub,
+    Shl,
+    Shr,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    BitAnd,
+    BitXor,
+    BitOr,
+    And,
+    Or
+}
+
+/// A parsed C preprocessor constant expression
+pub(crate) enum Expr<'a> {
+    Lit(CValue),
+    /// any identifier - not resolvable, so always non-foldable
+    Ident(&'a str),
+    Unary(UnOp, Box<Expr<'a>>),
+    Binary(Box<Expr<'a>>, BinOp, Box<Expr<'a>>),
+    Ternary(Box<Expr<'a>>, Box<Expr<'a>>, Box<Expr<'a>>)
+}
+
+impl Expr<'_> {
+    /// evaluate the expression, folding it to a single constant
+    ///
+    /// returns `None` if the expression references an identifier, divides
+    /// or modulos by zero, multiplies past `i128`, or shifts by a negative
+    /// or out-of-range count
+    pub(crate) fn eval(&self) -> Option<CValue> {
+	match self {
+	    Expr::Lit(v) => Some(*v),
+	    Expr::Ident(name) => {
+		// bind and discard rather than `_`: an unread `&str` field
+		// trips the dead-code lint
+		let _ = name;
+		None
+	    },
+	    Expr::Unary(op, e) => {
+		let v = e.eval()?;
+		match op {
+		    UnOp::Plus => Some(v),
+		    UnOp::Neg => Some(CValue { value: -v.value, ..v }),
+		    UnOp::BitNot => Some(CValue { value: !v.value, ..v }),
+		    UnOp::Not => Some(CValue::int(i128::from(v.value == 0)))
+		}
+	    },
+	    Expr::Binary(l, op, r) => {
+		let l = l.eval()?;
+		let r = r.eval()?;
+		// comparisons and logical operators always yield a plain `int`
+		if let Some(value) = Self::compare_or_logical(&l, op, &r) {
+		    return Some(CValue::int(value));
+		}
+		let (a, b, unsigned, long) = Self::fold_pair(l, r);
+		let value = match op {
+		    BinOp::Mul => a.checked_mul(b)?,
+		    BinOp::Div => {
+			if b == 0 {
+			    return None;
+			}
+			a / b
+		    },
+		    BinOp::Rem => {
+			if b == 0 {
+			    return None;
+			}
+			a % b
+		    },
+		    BinOp::Add => a + b,
+		    BinOp::Sub => a - b,
+		    BinOp::Shl => a.checked_shl(u32::try_from(b).ok()?)?,
+		    BinOp::Shr => a.checked_shr(u32::try_from(b).ok()?)?,
+		    BinOp::BitAnd => a & b,
+		    BinOp::BitXor => a ^ b,
+		    BinOp::BitOr => a | b,
+		    _ => return None
+		};
+		Some(CValue { value, unsigned, long })
+	    },
+	    Expr::Ternary(c, t, f) => {
+		if c.eval()?.value != 0 {
+		    t.eval()
+		} else {
+		    f.eval()
+		}
+	    }
+	}
+    }
+
+    fn fold_pair(a: CValue, b: CValue) -> (i128, i128, bool, bool) {
+	CValue::promote(a, b)
+    }
+
+    fn compare_or_logical(l: &CValue, op: &BinOp, r: &CValue) -> Option<i128> {
+	let (a, b) = (l.value, r.value);
+	Some(match op {
+	    BinOp::Lt => i128::from(a < b),
+	    BinOp::Le => i128::from(a <= b),
+	    BinOp::Gt => i128::from(a > b),
+	    BinOp::Ge => i128::from(a >= b),
+	    BinOp::Eq => i128::from(a == b),
+	    BinOp::Ne => i128::from(a != b),
+	    BinOp::And => i128::from(a != 0 && b != 0),
+	    BinOp::Or => i128::from(a != 0 || b != 0),
+	    _ => return None
+	})
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Tok<'a> {
+    Int(CValue),
+    Ident(&'a str),
+    Punct(&'a str)
+}
+
+fn lex(s: &str) -> Option<Vec<Tok<'_>>> {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    let mut out = Vec::new();
+    while i < bytes.len() {
+	let c = bytes[i];
+	if c.is_ascii_whitespace() {
+	    i += 1;
+	    continue;
+	}
+	if c.is_ascii_digit() {
+	    let start = i;
+	    if c == b'0' && i + 1 < bytes.len() && (bytes[i + 1] == b'x' || bytes[i + 1] == b'X') {
+		i += 2;
+		while i < bytes.len() && bytes[i].is_ascii_hexdigit() {
+		    i += 1;
+		}
+	    } else {
+		while i < bytes.len() && bytes[i].is_ascii_digit() {
+		    i += 1;
+		}
+	    }
+	    let digits_end = i;
+	    while i < bytes.len() && matches!(bytes[i], b'u' | b'U' | b'l' | b'L') {
+		i += 1;
+	    }
+	    out.push(Tok::Int(parse_int(&s[start..digits_end], &s[digits_end..i])?));
+	    continue;
+	}
+	if c.is_ascii_alphabetic() || c == b'_' {
+	    let start = i;
+	    while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+		i += 1;
+	    }
+	    out.push(Tok::Ident(&s[start..i]));
+	    continue;
+	}
+	let two = if i + 1 < bytes.len() { &s[i..i + 2] } else { "" };
+	if matches!(two, "<<" | ">>" | "<=" | ">=" | "==" | "!=" | "&&" | "||") {
+	    out.push(Tok::Punct(two));
+	    i += 2;
+	    continue;
+	}
+	if matches!(c, b'+' | b'-' | b'~' | b'!' | b'*' | b'/' | b'%' | b'<' | b'>'
+	    | b'&' | b'^' | b'|' | b'?' | b':' | b'(' | b')') {
+	    out.push(Tok::Punct(&s[i..i + 1]));
+	    i += 1;
+	    continue;
+	}
+	return None;
+    }
+    Some(out)
+}
+
+fn parse_int(digits: &str, suffix: &str) -> Option<CValue> {
+    let value = if let Some(hex) = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+	i128::from_str_radix(hex, 16).ok()?
+    } else if digits.len() > 1 && digits.starts_with('0') {
+	i128::from_str_radix(&digits[1..], 8).ok()?
+    } else {
+	digits.parse::<i128>().ok()?
+    };
+    let mut unsigned = suffix.chars().any(|c| c == 'u' || c == 'U');
+    let mut long = suffix.chars().any(|c| c == 'l' || c == 'L');
+    if value > i32::MAX as i128 {
+	long = true;
+    }
+    if value > i64::MAX as i128 {
+	unsigned = true;
+    }
+    Some(CValue { value, unsigned, long })
+}
+
+struct Parser<'a> {
+    toks: Vec<Tok<'a>>,
+    pos: usize
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Tok<'a>> {
+	self.toks.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<Tok<'a>> {
+	let t = self.peek();
+	self.pos += 1;
+	t
+    }
+
+    fn eat_punct(&mut self, p: &str) -> bool {
+	if let Some(Tok::Punct(found)) = self.peek() {
+	    if found == p {
+		self.pos += 1;
+		return true;
+	    }
+	}
+	false
+    }
+
+    fn ternary(&mut self) -> Option<Expr<'a>> {
+	let cond = self.logical_or()?;
+	if self.eat_punct("?") {
+	    let t = self.expr()?;
+	    if !self.eat_punct(":") {
+		return None;
+	    }
+	    let f = self.ternary()?;
+	    return Some(Expr::Ternary(Box::new(cond), Box::new(t), Box::new(f)));
+	}
+	Some(cond)
+    }
+
+    fn expr(&mut self) -> Option<Expr<'a>> {
+	self.ternary()
+    }
+
+    fn binary_level(&mut self, ops: &[(&str, BinOp)], next: fn(&mut Self) -> Option<Expr<'a>>) -> Option<Expr<'a>> {
+	let mut lhs = next(self)?;
+	'outer: loop {
+	    for (p, op) in ops {
+		if self.eat_punct(p) {
+		    let rhs = next(self)?;
+		    lhs = Expr::Binary(Box::new(lhs), *op, Box::new(rhs));
+		    continue 'outer;
+		}
+	    }
+	    return Some(lhs);
+	}
+    }
+
+    fn logical_or(&mut self) -> Option<Expr<'a>> {
+	self.binary_level(&[("||", BinOp::Or)], Self::logical_and)
+    }
+
+    fn logical_and(&mut self) -> Option<Expr<'a>> {
+	self.binary_level(&[("&&", BinOp::And)], Self::bit_or)
+    }
+
+    fn bit_or(&mut self) -> Option<Expr<'a>> {
+	self.binary_level(&[("|", BinOp::BitOr)], Self::bit_xor)
+    }
+
+    fn bit_xor(&mut self) -> Option<Expr<'a>> {
+	self.binary_level(&[("^", BinOp::BitXor)], Self::bit_and)
+    }
+
+    fn bit_and(&mut self) -> Option<Expr<'a>> {
+	self.binary_level(&[("&", BinOp::BitAnd)], Self::equality)
+    }
+
+    fn equality(&mut self) -> Option<Expr<'a>> {
+	self.binary_level(&[("==", BinOp::Eq), ("!=", BinOp::Ne)], Self::relational)
+    }
+
+    fn relational(&mut self) -> Option<Expr<'a>> {
+	self.binary_level(&[
+	    ("<=", BinOp::Le),
+	    (">=", BinOp::Ge),
+	    ("<", BinOp::Lt),
+	    (">", BinOp::Gt)
+	], Self::shift)
+    }
+
+    fn shift(&mut self) -> Option<Expr<'a>> {
+	self.binary_level(&[("<<", BinOp::Shl), (">>", BinOp::Shr)], Self::additive)
+    }
+
+    fn additive(&mut self) -> Option<Expr<'a>> {
+	self.binary_level(&[("+", BinOp::Add), ("-", BinOp::Sub)], Self::multiplicative)
+    }
+
+    fn multiplicative(&mut self) -> Option<Expr<'a>> {
+	self.binary_level(&[
+	    ("*", BinOp::Mul),
+	    ("/", BinOp::Div),
+	    ("%", BinOp::Rem)
+	], Self::unary)
+    }
+
+    fn unary(&mut self) -> Option<Expr<'a>> {
+	let op = match self.peek() {
+	    Some(Tok::Punct("+")) => Some(UnOp::Plus),
+	    Some(Tok::Punct("-")) => Some(UnOp::Neg),
+	    Some(Tok::Punct("~")) => Some(UnOp::BitNot),
+	    Some(Tok::Punct("!")) => Some(UnOp::Not),
+	    _ => None
+	};
+	if let Some(op) = op {
+	    self.pos += 1;
+	    let e = self.unary()?;
+	    return Some(Expr::Unary(op, Box::new(e)));
+	}
+	self.primary()
+    }
+
+    fn primary(&mut self) -> Option<Expr<'a>> {
+	match self.bump()? {
+	    Tok::Int(v) => Some(Expr::Lit(v)),
+	    Tok::Ident(name) => Some(Expr::Ident(name)),
+	    Tok::Punct("(") => {
+		let e = self.expr()?;
+		if !self.eat_punct(")") {
+		    return None;
+		}
+		Some(e)
+	    },
+	    _ => None
+	}
+    }
+}
+
+/// parse a C preprocessor constant expression, returning `None` on syntax
+/// errors or trailing input
+pub(crate) fn parse(s: &str) -> Option<Expr<'_>> {
+    let toks = lex(s)?;
+    let mut parser = Parser { toks, pos: 0 };
+    let e = parser.expr()?;
+    if parser.pos != parser.toks.len() {
+	return None;
+    }
+    Some(e)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse, CValue};
+
+    fn fold(s: &str) -> Option<i128> {
+	parse(s)?.eval().map(|v| v.value)
+    }
+
+    #[test]
+    fn literals() {
+	assert_eq!(fold("4"), Some(4));
+	assert_eq!(fold("0x10"), Some(16));
+	assert_eq!(fold("010"), Some(8));
+	assert_eq!(fold("4U"), Some(4));
+	assert_eq!(fold("4UL"), Some(4));
+    }
+
+    #[test]
+    fn arithmetic() {
+	assert_eq!(fold("(1 << 3) | 0x4"), Some(12));
+	assert_eq!(fold("1 + 2 * 3"), Some(7));
+	assert_eq!(fold("(1 + 2) * 3"), Some(9));
+	assert_eq!(fold("10 / 3"), Some(3));
+	assert_eq!(fold("10 % 3"), Some(1));
+	assert_eq!(fold("-5 + 2"), Some(-3));
+	assert_eq!(fold("~0"), Some(-1));
+	assert_eq!(fold("!0"), Some(1));
+    }
+
+    #[test]
+    fn comparisons_and_ternary() {
+	assert_eq!(fold("1 < 2"), Some(1));
+	assert_eq!(fold("1 == 2"), Some(0));
+	assert_eq!(fold("1 ? 2 : 3"), Some(2));
+	assert_eq!(fold("0 ? 2 : 3"), Some(3));
+	assert_eq!(fold("1 && 0 || 1"), Some(1));
+    }
+
+    #[test]
+    fn non_foldable() {
+	assert_eq!(fold("FOO"), None);
+	assert_eq!(fold("1 / 0"), None);
+	assert_eq!(fold("1 % 0"), None);
+	assert_eq!(fold("1 +"), None);
+	assert_eq!(fold("1 << -1"), None);
+	assert_eq!(fold("1 << 200"), None);
+	assert_eq!(fold("1 >> -1"), None);
+	assert_eq!(fold("1 >> 200"), None);
+    }
+
+    #[test]
+    fn suffix_tokens() {
+	assert_eq!(&CValue { value: 4, unsigned: false, long: false }.token(), "4");
+	assert_eq!(&CValue { value: 4, unsigned: false, long: true }.token(), "4L");
+	assert_eq!(&CValue { value: 4, unsigned: true, long: false }.token(), "4U");
+	assert_eq!(&CValue { value: 4, unsigned: true, long: true }.token(), "4UL");
+    }
+}
@@ -0,0 +1,387 @@
+/*
+
+BSD 3-Clause License
+
+Copyright (c) 2025, Isaac Budzik
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the following conditions are met:
+
+1. Redistributions of source code must retain the above copyright notice, this
+   list of conditions and the following disclaimer.
+
+2. Redistributions in binary form must reproduce the above copyright notice,
+   this list of conditions and the following disclaimer in the documentation
+   and/or other materials provided with the distribution.
+
+3. Neither the name of the copyright holder nor the names of its
+   contributors may be used to endorse or promote products derived from
+   this software without specific prior written permission.
+
+THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+*/
+
+//! Parse an existing C header back into ccgen structures
+//!
+//! This is the reverse of the `tok::Token` direction: [`parse`] lexes a
+//! header into statements (honoring line continuations, `//`/`/* */`
+//! comments, and string/char literals) and walks them in a single flat
+//! pass, without descending into nested blocks, to recognize the subset of C a `Header` can represent: an `#ifndef`/
+//! `#define`/`#endif` guard triple, the `extern "C"`/`__cplusplus` wrappers
+//! that select a `CXX` variant, `#define` macros, `typedef`s, and function
+//! prototypes. Anything else is preserved verbatim in `Header::extra` (or
+//! `Header::post_extra`, once past the guard's closing `#endif`) so the
+//! result still round-trips through `Token`.
+//!
+//! Struct/union/enum declarations are not recognized by this pass.
+//!
+//! `Header`'s fields borrow from the caller's arrays, so a parsed result
+//! that owns its own storage (the declaration arrays, and any `extra`/
+//! `post_extra` text stitched together from multiple unclassified lines)
+//! leaks that storage to satisfy the borrow - acceptable for the one-shot,
+//! build-time use this parser is meant for.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use alloc::boxed::Box;
+
+use crate::{Header, Macro, Type, Func, HeaderGuard, CXX, Variadic};
+
+fn push_statement<'a>(out: &mut Vec<&'a str>, s: &'a str) {
+    let s = s.trim();
+    if !s.is_empty() {
+	out.push(s);
+    }
+}
+
+/// split a header into its top-level statements: `#`-directives (one per
+/// line, honoring `\`-continuation), and everything else terminated by
+/// `;`, `{`, or `}`
+fn statements(src: &str) -> Vec<&str> {
+    let bytes = src.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+    let mut start = 0;
+    while i < bytes.len() {
+	match bytes[i] {
+	    b'\\' if matches!(bytes.get(i + 1), Some(b'\n')) => i += 2,
+	    b'/' if matches!(bytes.get(i + 1), Some(b'/')) => {
+		while i < bytes.len() && bytes[i] != b'\n' {
+		    i += 1;
+		}
+	    },
+	    b'/' if matches!(bytes.get(i + 1), Some(b'*')) => {
+		i += 2;
+		while i + 1 < bytes.len() && !(bytes[i] == b'*' && bytes[i + 1] == b'/') {
+		    i += 1;
+		}
+		i = (i + 2).min(bytes.len());
+	    },
+	    b'"' | b'\'' => {
+		let quote = bytes[i];
+		i += 1;
+		while i < bytes.len() && bytes[i] != quote {
+		    i += if bytes[i] == b'\\' { 2 } else { 1 };
+		}
+		i = (i + 1).min(bytes.len());
+	    },
+	    b'#' => {
+		let dstart = i;
+		i += 1;
+		while i < bytes.len() {
+		    if bytes[i] == b'\\' && matches!(bytes.get(i + 1), Some(b'\n')) {
+			i += 2;
+			continue;
+		    }
+		    if bytes[i] == b'\n' {
+			break;
+		    }
+		    i += 1;
+		}
+		push_statement(&mut out, &src[dstart..i]);
+		start = i;
+	    },
+	    b';' | b'{' | b'}' => {
+		push_statement(&mut out, &src[start..=i]);
+		i += 1;
+		start = i;
+	    },
+	    _ => i += 1
+	}
+    }
+    push_statement(&mut out, &src[start..]);
+    out
+}
+
+/// split a `#define TOK VAL` (or `#define TOK` with no value) directive
+fn split_define(rest: &str) -> (&str, &str) {
+    let rest = rest.trim_start();
+    if let Some(paren) = rest.find('(') {
+	if rest[..paren].chars().next().is_some_and(|c| c.is_alphanumeric() || c == '_')
+	    && !rest[..paren].contains(char::is_whitespace)
+	{
+	    if let Some(close) = rest.find(')') {
+		return (rest[..=close].trim(), rest[close + 1..].trim());
+	    }
+	}
+    }
+    match rest.find(char::is_whitespace) {
+	Some(sp) => (rest[..sp].trim(), rest[sp..].trim()),
+	None => (rest.trim(), "")
+    }
+}
+
+/// split `typedef TYPE NAME;` into `(TYPE, NAME)`
+fn split_typedef(body: &str) -> Option<(&str, &str)> {
+    let body = body.trim().trim_end_matches(';').trim();
+    let name_start = body.rfind(|c: char| !(c.is_alphanumeric() || c == '_'))? + 1;
+    if name_start >= body.len() {
+	return None;
+    }
+    Some((body[..name_start].trim(), &body[name_start..]))
+}
+
+/// split `RET NAME(PARAMS);` into `(RET, NAME, PARAMS-text)`
+fn split_func(body: &str) -> Option<(&str, &str, &str)> {
+    let body = body.trim().trim_end_matches(';').trim();
+    let open = body.find('(')?;
+    let close = body.rfind(')')?;
+    if close < open {
+	return None;
+    }
+    let head = body[..open].trim();
+    let name_start = head.rfind(|c: char| !(c.is_alphanumeric() || c == '_'))? + 1;
+    if name_start >= head.len() {
+	return None;
+    }
+    Some((head[..name_start].trim(), &head[name_start..], body[open + 1..close].trim()))
+}
+
+fn split_params(params: &str) -> (Vec<&str>, Variadic) {
+    if params.is_empty() {
+	return (Vec::new(), Variadic::Nary);
+    }
+    let mut parts: Vec<&str> = params.split(',').map(str::trim).collect();
+    let va = if parts.last() == Some(&"...") {
+	parts.pop();
+	Variadic::Variadic
+    } else {
+	Variadic::Nary
+    };
+    (parts, va)
+}
+
+/// parse a C header string into a `Header`
+///
+/// always returns `Some`; input with no recognizable content yields a
+/// `Header` with every field empty rather than `None`
+pub fn parse(src: &str) -> Option<Header<'_>> {
+    let stmts = statements(src);
+    let mut guard: Option<HeaderGuard<'_>> = None;
+    let mut cxx = CXX::C;
+    let mut funcs = Vec::new();
+    let mut macros = Vec::new();
+    let mut types = Vec::new();
+    let mut extra = String::new();
+    let mut post_extra = String::new();
+    let mut guard_closed = false;
+    let guard_end = stmts.iter().rposition(|s| *s == "#endif");
+
+    let mut i = 0;
+    if let [first, second, ..] = stmts[..] {
+	if let Some(tok) = first.strip_prefix("#ifndef ").map(str::trim) {
+	    if let Some(def) = second.strip_prefix("#define ") {
+		let (dtok, dval) = split_define(def);
+		if dtok == tok {
+		    guard = Some(HeaderGuard::new(tok, dval));
+		    i = 2;
+		}
+	    }
+	}
+    }
+
+    while i < stmts.len() {
+	let s = stmts[i];
+	if s == "#ifdef __cplusplus" {
+	    match stmts.get(i + 1..i + 3) {
+		Some(["extern \"C\" {", "#endif"]) => {
+		    cxx = CXX::CXX;
+		    i += 3;
+		    continue;
+		},
+		Some([err, "#endif"]) if err.starts_with("#error") => {
+		    cxx = CXX::C;
+		    i += 3;
+		    continue;
+		},
+		_ => {}
+	    }
+	}
+	if s == "#ifndef __cplusplus" {
+	    if let Some([err, "#endif"]) = stmts.get(i + 1..i + 3) {
+		if err.starts_with("#error") {
+		    cxx = CXX::CXXOnly;
+		    i += 3;
+		    continue;
+		}
+	    }
+	}
+	// closing `extern "C"` wrapper: `#ifdef __cplusplus` / `}` / `#endif`
+	if s == "#ifdef __cplusplus" {
+	    if let Some(["}", "#endif"]) = stmts.get(i + 1..i + 3) {
+		i += 3;
+		continue;
+	    }
+	}
+	if s == "#endif" && guard.is_some() && Some(i) == guard_end {
+	    guard_closed = true;
+	    i += 1;
+	    continue;
+	}
+	if let Some(def) = s.strip_prefix("#define ") {
+	    let (tok, val) = split_define(def);
+	    macros.push(Macro::new(tok, val, None));
+	    i += 1;
+	    continue;
+	}
+	if let Some(body) = s.strip_prefix("typedef ") {
+	    if let Some((r#type, name)) = split_typedef(body) {
+		types.push(Type::new(name, r#type, None));
+		i += 1;
+		continue;
+	    }
+	}
+	if !s.starts_with('#') && !s.starts_with("typedef ") && s.ends_with(';') {
+	    if let Some((out, name, params)) = split_func(s) {
+		let (params, va) = split_params(params);
+		funcs.push(Func::from_strs(out, name, Box::leak(params.into_boxed_slice()), va, None));
+		i += 1;
+		continue;
+	    }
+	}
+	let buf = if guard_closed { &mut post_extra } else { &mut extra };
+	if !buf.is_empty() {
+	    buf.push('\n');
+	}
+	buf.push_str(s);
+	i += 1;
+    }
+
+    let extra = if extra.is_empty() { None } else { Some(&*String::leak(extra)) };
+    let post_extra = if post_extra.is_empty() { None } else { Some(&*String::leak(post_extra)) };
+
+    Some(Header::new(
+	None,
+	"",
+	guard,
+	Box::leak(funcs.into_boxed_slice()),
+	Box::leak(macros.into_boxed_slice()),
+	Box::leak(types.into_boxed_slice()),
+	&[],
+	&[],
+	&[],
+	&[],
+	&[],
+	cxx,
+	extra,
+	post_extra
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse;
+    use super::super::Token;
+
+    #[test]
+    fn object_macro() {
+	let h = parse("#define H 1\n").unwrap();
+	assert_eq!(h.macros().len(), 1);
+	assert_eq!(h.macros()[0].tok(), "H");
+	assert_eq!(h.macros()[0].val(), "1");
+    }
+
+    #[test]
+    fn function_macro() {
+	let h = parse("#define MAX(a, b) ((a) > (b) ? (a) : (b))\n").unwrap();
+	assert_eq!(h.macros()[0].tok(), "MAX(a, b)");
+	assert_eq!(h.macros()[0].val(), "((a) > (b) ? (a) : (b))");
+    }
+
+    #[test]
+    fn typedef() {
+	let h = parse("typedef unsigned long size_t;\n").unwrap();
+	assert_eq!(h.types()[0].name(), "size_t");
+	assert_eq!(h.types()[0].r#type(), "unsigned long");
+    }
+
+    #[test]
+    fn func_proto() {
+	let h = parse("int printf(const char*, ...);\n").unwrap();
+	assert_eq!(h.funcs()[0].out(), "int");
+	assert_eq!(h.funcs()[0].name(), "printf");
+	assert_eq!(h.funcs()[0].params()[0].r#type(), "const char*");
+	assert_eq!(h.funcs()[0].va(), super::super::super::Variadic::Variadic);
+    }
+
+    #[test]
+    fn roundtrip() {
+	let src = "#ifdef __cplusplus\nextern \"C\" {\n#endif\n\ntypedef unsigned long size_t;\n\n#define H 1\n\nint printf(const char*, ...);\n\n\n#ifdef __cplusplus\n}\n#endif\n\n";
+	let h = parse(src).unwrap();
+	assert_eq!(h.types()[0].name(), "size_t");
+	assert_eq!(h.macros()[0].tok(), "H");
+	assert_eq!(h.funcs()[0].name(), "printf");
+	assert_eq!(&h.token(), src);
+    }
+
+    #[test]
+    fn roundtrip_cxx_c() {
+	let src = "#ifdef __cplusplus\n#error \"This header can only be used by C\"\n#endif\n\ntypedef unsigned long size_t;\n\n#define H 1\n\nint printf(...);\n\n\n\n";
+	let h = parse(src).unwrap();
+	assert_eq!(h.types()[0].name(), "size_t");
+	assert_eq!(h.macros()[0].tok(), "H");
+	assert_eq!(h.funcs()[0].name(), "printf");
+	assert_eq!(h.extra(), None);
+	assert_eq!(&h.token(), src);
+    }
+
+    #[test]
+    fn roundtrip_cxx_only() {
+	let src = "#ifndef __cplusplus\n#error \"This header can only be used by C++\"\n#endif\n\ntypedef unsigned long size_t;\n\n#define H 1\n\nint printf(...);\n\n\n\n";
+	let h = parse(src).unwrap();
+	assert_eq!(h.types()[0].name(), "size_t");
+	assert_eq!(h.macros()[0].tok(), "H");
+	assert_eq!(h.funcs()[0].name(), "printf");
+	assert_eq!(h.extra(), None);
+	assert_eq!(&h.token(), src);
+    }
+
+    #[test]
+    fn function_pointer_typedef_falls_through_to_extra() {
+	let h = parse("typedef void (*cb)(int);\n").unwrap();
+	assert!(h.types().is_empty());
+	assert!(h.funcs().is_empty());
+	assert_eq!(h.extra(), Some("typedef void (*cb)(int);"));
+    }
+
+    #[test]
+    fn header_guard_and_extra() {
+	let src = "#ifndef FOO_H\n#define FOO_H\n#pragma once\ntypedef int foo_t;\n#endif\n";
+	let h = parse(src).unwrap();
+	let guard = h.guard().unwrap();
+	assert_eq!(guard.tok(), "FOO_H");
+	assert_eq!(guard.val(), "");
+	assert_eq!(h.extra(), Some("#pragma once"));
+	assert_eq!(h.types()[0].name(), "foo_t");
+    }
+}
@@ -0,0 +1,243 @@
+/*
+
+BSD 3-Clause License
+
+Copyright (c) 2025, Isaac Budzik
+
+Redistribution and use in source and binary forms, with or without
+modification, are permitted provided that the following conditions are met:
+
+1. Redistributions of source code must retain the above copyright notice, this
+   list of conditions and the following disclaimer.
+
+2. Redistributions in binary form must reproduce the above copyright notice,
+   this list of conditions and the following disclaimer in the documentation
+   and/or other materials provided with the distribution.
+
+3. Neither the name of the copyright holder nor the names of its
+   contributors may be used to endorse or promote products derived from
+   this software without specific prior written permission.
+
+THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+
+*/
+
+//! Dependency-aware ordering of typedef/struct/union/enum declarations
+//!
+//! `Token for Header` emits these in raw array order, which breaks when a
+//! later declaration is referenced by an earlier one. [`sorted_decls`]
+//! topologically sorts them by scanning each declaration's type text for
+//! whole-word references to another declaration's name, via a DFS with a
+//! visited/on-stack marker; a back-edge (dependency cycle) falls back to a
+//! forward declaration for the cycle-closing target instead of failing.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{Header, Type, Struct, Union, Enum, Cfg};
+use super::{Token, EndToken};
+
+#[derive(Clone, Copy)]
+enum Decl<'a> {
+    Type(Type<'a>),
+    Struct(Struct<'a>),
+    Union(Union<'a>),
+    Enum(Enum<'a>)
+}
+
+impl<'a> Decl<'a> {
+    fn name(&self) -> &'a str {
+	match self {
+	    Decl::Type(t) => t.name(),
+	    Decl::Struct(s) => s.name(),
+	    Decl::Union(u) => u.name(),
+	    Decl::Enum(e) => e.name()
+	}
+    }
+
+    fn cfg(&self) -> Option<Cfg<'a>> {
+	match self {
+	    Decl::Type(t) => t.cfg(),
+	    Decl::Struct(s) => s.cfg(),
+	    Decl::Union(u) => u.cfg(),
+	    Decl::Enum(e) => e.cfg()
+	}
+    }
+
+    fn token(&self) -> String {
+	match self {
+	    Decl::Type(t) => t.token(),
+	    Decl::Struct(s) => s.token(),
+	    Decl::Union(u) => u.token(),
+	    Decl::Enum(e) => e.token()
+	}
+    }
+
+    /// type text that may reference another declaration by name
+    fn type_strs(&self) -> Vec<&'a str> {
+	match self {
+	    Decl::Type(t) => Vec::from([t.r#type()]),
+	    Decl::Struct(s) => s.fields().iter().map(|f| f.r#type()).collect(),
+	    Decl::Union(u) => u.fields().iter().map(|f| f.r#type()).collect(),
+	    Decl::Enum(_) => Vec::new()
+	}
+    }
+
+    /// forward-declaration stub used to break a dependency cycle
+    fn forward(&self) -> String {
+	let mut out = String::new();
+	match self {
+	    Decl::Struct(s) => {
+		out.push_str("struct ");
+		out.push_str(s.name());
+		out.push_str(";\n");
+	    },
+	    Decl::Union(u) => {
+		out.push_str("union ");
+		out.push_str(u.name());
+		out.push_str(";\n");
+	    },
+	    Decl::Type(t) => {
+		let ty = t.r#type();
+		let tag = ["struct ", "union ", "enum "].iter().find_map(|kw| {
+		    ty.strip_prefix(kw).filter(|rest| !rest.is_empty() && !rest.contains(char::is_whitespace))
+		});
+		if tag.is_some() {
+		    out.push_str("typedef ");
+		    out.push_str(ty);
+		    out.push(' ');
+		    out.push_str(t.name());
+		    out.push_str(";\n");
+		} else {
+		    out.push_str("struct ");
+		    out.push_str(t.name());
+		    out.push_str(";\n");
+		}
+	    },
+	    Decl::Enum(_) => {}
+	}
+	out
+    }
+}
+
+enum Item<'a> {
+    Decl(Decl<'a>),
+    Forward(String)
+}
+
+/// whether `word` occurs in `haystack` as a whole word (not as part of a
+/// longer identifier)
+fn contains_word(haystack: &str, word: &str) -> bool {
+    if word.is_empty() {
+	return false;
+    }
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let mut start = 0;
+    while let Some(pos) = haystack[start..].find(word) {
+	let idx = start + pos;
+	let before_ok = haystack[..idx].chars().next_back().is_none_or(|c| !is_word_char(c));
+	let after_ok = haystack[idx + word.len()..].chars().next().is_none_or(|c| !is_word_char(c));
+	if before_ok && after_ok {
+	    return true;
+	}
+	start = idx + 1;
+    }
+    false
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Mark {
+    Unvisited,
+    OnStack,
+    Done
+}
+
+#[allow(clippy::too_many_arguments)]
+fn visit<'a>(
+    i: usize,
+    decls: &[Decl<'a>],
+    deps: &[Vec<usize>],
+    mark: &mut [Mark],
+    forwarded: &mut [bool],
+    order: &mut Vec<Item<'a>>
+) {
+    mark[i] = Mark::OnStack;
+    for &j in &deps[i] {
+	match mark[j] {
+	    Mark::Unvisited => visit(j, decls, deps, mark, forwarded, order),
+	    Mark::OnStack => {
+		if !forwarded[j] {
+		    order.push(Item::Forward(decls[j].forward()));
+		    forwarded[j] = true;
+		}
+	    },
+	    Mark::Done => {}
+	}
+    }
+    mark[i] = Mark::Done;
+    order.push(Item::Decl(decls[i]));
+}
+
+fn render(items: &[Item<'_>]) -> String {
+    let mut out = String::new();
+    let mut current: Option<Cfg<'_>> = None;
+    for item in items {
+	let cfg = match item {
+	    Item::Decl(d) => d.cfg(),
+	    Item::Forward(_) => None
+	};
+	if current.is_some() && cfg != current {
+	    out.push_str(&current.take().unwrap().end_token());
+	}
+	if current.is_none() {
+	    if let Some(c) = cfg {
+		out.push_str(&c.token());
+		current = Some(c);
+	    }
+	}
+	match item {
+	    Item::Decl(d) => out.push_str(&d.token()),
+	    Item::Forward(s) => out.push_str(s)
+	}
+    }
+    if let Some(c) = current {
+	out.push_str(&c.end_token());
+    }
+    out
+}
+
+/// topologically sort `header`'s typedefs/structs/unions/enums and render
+/// them, falling back to a forward declaration to break any cycle
+pub(crate) fn sorted_decls(header: &Header<'_>) -> String {
+    let mut decls = Vec::new();
+    decls.extend(header.types().iter().map(|t| Decl::Type(*t)));
+    decls.extend(header.structs().iter().map(|s| Decl::Struct(*s)));
+    decls.extend(header.unions().iter().map(|u| Decl::Union(*u)));
+    decls.extend(header.enums().iter().map(|e| Decl::Enum(*e)));
+
+    let deps: Vec<Vec<usize>> = decls.iter().enumerate().map(|(i, d)| {
+	let types = d.type_strs();
+	decls.iter().enumerate().filter(|(j, other)| {
+	    *j != i && types.iter().any(|ty| contains_word(ty, other.name()))
+	}).map(|(j, _)| j).collect()
+    }).collect();
+
+    let mut mark: Vec<Mark> = decls.iter().map(|_| Mark::Unvisited).collect();
+    let mut forwarded: Vec<bool> = decls.iter().map(|_| false).collect();
+    let mut order = Vec::new();
+    for i in 0..decls.len() {
+	if mark[i] == Mark::Unvisited {
+	    visit(i, &decls, &deps, &mut mark, &mut forwarded, &mut order);
+	}
+    }
+    render(&order)
+}